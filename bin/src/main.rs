@@ -2,9 +2,19 @@
 
 use clap::Parser;
 
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Units {
+    Metric,
+    Imperial,
+}
+
 #[derive(Parser)]
 struct Opt {
     device: String,
+
+    /// Unit system to render readings in.
+    #[arg(long, value_enum, default_value_t = Units::Metric)]
+    units: Units,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -12,9 +22,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let ws2300 = ws2300::Device::new(opt.device)?;
 
-    let data = ws2300.read_all()?;
-
-    let json = serde_json::to_string(&data)?;
+    let json = match opt.units {
+        Units::Metric => serde_json::to_string(&ws2300.read_all()?)?,
+        #[cfg(feature = "units")]
+        Units::Imperial => serde_json::to_string(&ws2300::units::read_all(
+            &ws2300,
+            ws2300::units::System::Imperial,
+        )?)?,
+        #[cfg(not(feature = "units"))]
+        Units::Imperial => return Err("imperial units require the `units` feature".into()),
+    };
     println!("{json}");
 
     Ok(())
@@ -0,0 +1,253 @@
+//! Data-driven description of the WS2300 memory map.
+//!
+//! `MemoryMap` used to be a fixed struct of hardcoded `(address, size)`
+//! pairs, one per sensor, baked into `Device::new`. That made it impossible
+//! to point `Device` at a firmware variant (or another Lacrosse-family
+//! station) with shifted addresses, or to add a sensor the crate doesn't
+//! already know about, without recompiling. `MemoryMap::from_toml` loads
+//! the same information from a table of named registers instead, each
+//! giving an `address`, `size` and a `decoder` kind (see `RegisterKind`).
+
+use crate::{Device, Memory, Reading};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Which nibble of the first data byte an `enum` register's label index
+/// lives in (see `tendency`/`forecast`, which share a byte).
+#[derive(Clone, Copy, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Nibble {
+    High,
+    Low,
+}
+
+/// How to turn a register's raw bytes into a `Reading`.
+#[derive(Clone, serde::Deserialize)]
+#[serde(tag = "decoder", rename_all = "snake_case")]
+pub enum RegisterKind {
+    Temperature,
+    Humidity,
+    BcdRain,
+    Pressure,
+    WindSpeed,
+    WindDirDegrees,
+    /// A label looked up by the index held in one nibble of the first byte,
+    /// e.g. the compass rose or the tendency/forecast tables.
+    Enum { nibble: Nibble, labels: Vec<String> },
+}
+
+impl RegisterKind {
+    pub(crate) fn decode(&self, value: &[u8]) -> serialport::Result<Reading> {
+        Ok(match self {
+            RegisterKind::Temperature => Reading::Number(Device::decode_temperature(value)),
+            RegisterKind::Humidity => Reading::Number(Device::decode_humidity(value) as f32),
+            RegisterKind::BcdRain => Reading::Number(Device::decode_rain(value)),
+            RegisterKind::Pressure => Reading::Number(Device::decode_pressure(value)),
+            RegisterKind::WindSpeed => Reading::Number(Device::decode_wind_speed(value)),
+            RegisterKind::WindDirDegrees => Reading::Number(Device::decode_wind_dir(value)),
+            RegisterKind::Enum { nibble, labels } => {
+                let index = match nibble {
+                    Nibble::High => (value[0] >> 4) as usize,
+                    Nibble::Low => (value[0] & 0xF) as usize,
+                };
+
+                let label = labels.get(index).ok_or_else(|| {
+                    serialport::Error::new(
+                        serialport::ErrorKind::Io(std::io::ErrorKind::InvalidData),
+                        format!("label index {index} out of range for a {}-entry enum register", labels.len()),
+                    )
+                })?;
+
+                Reading::Text(label.clone())
+            }
+        })
+    }
+
+    /// Fewest bytes this decoder needs to read `value[0]` (and, for the
+    /// multi-byte kinds, `value[1]`/`value[2]`) without panicking.
+    fn min_size(&self) -> usize {
+        match self {
+            RegisterKind::Temperature => 2,
+            RegisterKind::Humidity => 1,
+            RegisterKind::BcdRain => 3,
+            RegisterKind::Pressure => 3,
+            RegisterKind::WindSpeed => 2,
+            RegisterKind::WindDirDegrees => 1,
+            RegisterKind::Enum { .. } => 1,
+        }
+    }
+}
+
+/// A single named register: where it lives and how to decode it.
+#[derive(Clone, serde::Deserialize)]
+pub struct RegisterSpec {
+    pub address: u32,
+    pub size: usize,
+    #[serde(flatten)]
+    pub kind: RegisterKind,
+}
+
+impl RegisterSpec {
+    pub(crate) fn memory(&self) -> Memory {
+        Memory {
+            address: self.address,
+            size: self.size,
+        }
+    }
+}
+
+/// Named registers a `Device` can read, keyed by name (`temperature_indoor`,
+/// `wind_direction`, ...). Build one with `MemoryMap::default()` for the
+/// addresses the real WS2300 uses, or `MemoryMap::from_toml` to point at a
+/// differently-addressed station or add sensors the crate doesn't ship.
+pub struct MemoryMap {
+    registers: BTreeMap<String, RegisterSpec>,
+}
+
+impl MemoryMap {
+    /// Load a memory map from a TOML table of named registers, e.g.:
+    ///
+    /// ```toml
+    /// [temperature_indoor]
+    /// address = 0x346
+    /// size = 2
+    /// decoder = "temperature"
+    ///
+    /// [wind_direction]
+    /// address = 0x52C
+    /// size = 1
+    /// decoder = "enum"
+    /// nibble = "high"
+    /// labels = ["N", "NNE", "NE", "ENE"]
+    /// ```
+    pub fn from_toml(path: impl AsRef<Path>) -> serialport::Result<MemoryMap> {
+        let contents = std::fs::read_to_string(path)?;
+        let registers: BTreeMap<String, RegisterSpec> = toml::from_str(&contents).map_err(|e| {
+            serialport::Error::new(
+                serialport::ErrorKind::Io(std::io::ErrorKind::InvalidData),
+                e.to_string(),
+            )
+        })?;
+
+        for (name, spec) in &registers {
+            let min_size = spec.kind.min_size();
+
+            if spec.size < min_size {
+                return Err(serialport::Error::new(
+                    serialport::ErrorKind::Io(std::io::ErrorKind::InvalidData),
+                    format!(
+                        "register '{name}' has size {} but its decoder needs at least {min_size}",
+                        spec.size
+                    ),
+                ));
+            }
+        }
+
+        Ok(MemoryMap { registers })
+    }
+
+    pub(crate) fn get(&self, name: &str) -> serialport::Result<&RegisterSpec> {
+        self.registers.get(name).ok_or_else(|| {
+            serialport::Error::new(
+                serialport::ErrorKind::Io(std::io::ErrorKind::NotFound),
+                format!("unknown register '{name}'"),
+            )
+        })
+    }
+}
+
+impl Default for MemoryMap {
+    fn default() -> MemoryMap {
+        let mut registers = BTreeMap::new();
+
+        registers.insert(
+            "temperature_indoor".to_string(),
+            RegisterSpec { address: 0x346, size: 2, kind: RegisterKind::Temperature },
+        );
+        registers.insert(
+            "temperature_outdoor".to_string(),
+            RegisterSpec { address: 0x373, size: 2, kind: RegisterKind::Temperature },
+        );
+        registers.insert(
+            "dewpoint".to_string(),
+            RegisterSpec { address: 0x3CE, size: 2, kind: RegisterKind::Temperature },
+        );
+        registers.insert(
+            "humidity_indoor".to_string(),
+            RegisterSpec { address: 0x3FB, size: 1, kind: RegisterKind::Humidity },
+        );
+        registers.insert(
+            "humidity_outdoor".to_string(),
+            RegisterSpec { address: 0x419, size: 1, kind: RegisterKind::Humidity },
+        );
+        registers.insert(
+            "wind_speed".to_string(),
+            RegisterSpec { address: 0x529, size: 3, kind: RegisterKind::WindSpeed },
+        );
+        registers.insert(
+            "wind_dir_degrees".to_string(),
+            RegisterSpec { address: 0x52C, size: 1, kind: RegisterKind::WindDirDegrees },
+        );
+        registers.insert(
+            "wind_direction".to_string(),
+            RegisterSpec {
+                address: 0x52C,
+                size: 1,
+                kind: RegisterKind::Enum {
+                    nibble: Nibble::High,
+                    labels: [
+                        "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW",
+                        "W", "WNW", "NW", "NNW",
+                    ]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+                },
+            },
+        );
+        registers.insert(
+            "wind_chill".to_string(),
+            RegisterSpec { address: 0x3A0, size: 2, kind: RegisterKind::Temperature },
+        );
+        registers.insert(
+            "rain_1h".to_string(),
+            RegisterSpec { address: 0x4B4, size: 3, kind: RegisterKind::BcdRain },
+        );
+        registers.insert(
+            "rain_24h".to_string(),
+            RegisterSpec { address: 0x497, size: 3, kind: RegisterKind::BcdRain },
+        );
+        registers.insert(
+            "rain_total".to_string(),
+            RegisterSpec { address: 0x4D2, size: 3, kind: RegisterKind::BcdRain },
+        );
+        registers.insert(
+            "pressure".to_string(),
+            RegisterSpec { address: 0x5E2, size: 3, kind: RegisterKind::Pressure },
+        );
+        registers.insert(
+            "tendency".to_string(),
+            RegisterSpec {
+                address: 0x26B,
+                size: 1,
+                kind: RegisterKind::Enum {
+                    nibble: Nibble::High,
+                    labels: vec!["Steady".to_string(), "Rising".to_string(), "Falling".to_string()],
+                },
+            },
+        );
+        registers.insert(
+            "forecast".to_string(),
+            RegisterSpec {
+                address: 0x26B,
+                size: 1,
+                kind: RegisterKind::Enum {
+                    nibble: Nibble::Low,
+                    labels: vec!["Rainy".to_string(), "Cloudy".to_string(), "Sunny".to_string()],
+                },
+            },
+        );
+
+        MemoryMap { registers }
+    }
+}
@@ -1,6 +1,7 @@
 #![warn(warnings)]
 
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::thread::sleep;
 use std::time::Duration;
 use std::env;
@@ -13,8 +14,130 @@ macro_rules! dbg_ws {
     };
 }
 
-pub struct Device {
+#[cfg(feature = "units")]
+pub mod units;
+
+mod config;
+pub use config::{MemoryMap, Nibble, RegisterKind, RegisterSpec};
+
+/// Byte-level access to the wire the WS2300 protocol is spoken over.
+///
+/// `Device` drives the protocol state machine (`read`/`check`/`check_data`/`reset`)
+/// against this trait rather than against `serialport` directly, so the state
+/// machine can be exercised offline with `MockTransport` or pointed at something
+/// other than a local serial port (e.g. a serial-over-IP bridge).
+pub trait Transport {
+    fn write_all(&self, buf: &[u8]) -> serialport::Result<()>;
+    fn read_exact(&self, buf: &mut [u8]) -> serialport::Result<()>;
+    fn flush(&self) -> serialport::Result<()>;
+    fn write_request_to_send(&self, level: bool) -> serialport::Result<()>;
+    fn write_data_terminal_ready(&self, level: bool) -> serialport::Result<()>;
+}
+
+/// Default `Transport` backed by a real `serialport::SerialPort`.
+pub struct SerialTransport {
     port: RefCell<Box<dyn serialport::SerialPort>>,
+}
+
+impl SerialTransport {
+    pub fn open(device: String) -> serialport::Result<SerialTransport> {
+        let port = serialport::new(&device, 2_400)
+            .data_bits(serialport::DataBits::Eight)
+            .flow_control(serialport::FlowControl::None)
+            .parity(serialport::Parity::None)
+            .stop_bits(serialport::StopBits::One)
+            .timeout(Duration::from_secs(1))
+            .open()?;
+
+        let transport = SerialTransport {
+            port: RefCell::new(port),
+        };
+
+        transport.write_request_to_send(true)?;
+        transport.write_data_terminal_ready(false)?;
+
+        Ok(transport)
+    }
+}
+
+impl Transport for SerialTransport {
+    fn write_all(&self, buf: &[u8]) -> serialport::Result<()> {
+        Ok(self.port.borrow_mut().write_all(buf)?)
+    }
+
+    fn read_exact(&self, buf: &mut [u8]) -> serialport::Result<()> {
+        Ok(self.port.borrow_mut().read_exact(buf)?)
+    }
+
+    fn flush(&self) -> serialport::Result<()> {
+        Ok(self.port.borrow_mut().flush()?)
+    }
+
+    fn write_request_to_send(&self, level: bool) -> serialport::Result<()> {
+        self.port.borrow_mut().write_request_to_send(level)
+    }
+
+    fn write_data_terminal_ready(&self, level: bool) -> serialport::Result<()> {
+        self.port.borrow_mut().write_data_terminal_ready(level)
+    }
+}
+
+/// `Transport` that replays a canned sequence of response bytes instead of
+/// talking to real hardware, so the `read`/`check`/`check_data`/`reset` state
+/// machine can be unit-tested offline.
+pub struct MockTransport {
+    reads: RefCell<VecDeque<u8>>,
+    writes: RefCell<Vec<u8>>,
+}
+
+impl MockTransport {
+    pub fn new(reads: impl IntoIterator<Item = u8>) -> MockTransport {
+        MockTransport {
+            reads: RefCell::new(reads.into_iter().collect()),
+            writes: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Every byte written to the transport so far, in order.
+    pub fn written(&self) -> Vec<u8> {
+        self.writes.borrow().clone()
+    }
+}
+
+impl Transport for MockTransport {
+    fn write_all(&self, buf: &[u8]) -> serialport::Result<()> {
+        self.writes.borrow_mut().extend_from_slice(buf);
+        Ok(())
+    }
+
+    fn read_exact(&self, buf: &mut [u8]) -> serialport::Result<()> {
+        for byte in buf.iter_mut() {
+            *byte = self.reads.borrow_mut().pop_front().ok_or_else(|| {
+                serialport::Error::new(
+                    serialport::ErrorKind::Io(std::io::ErrorKind::UnexpectedEof),
+                    "MockTransport exhausted",
+                )
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&self) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn write_request_to_send(&self, _level: bool) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn write_data_terminal_ready(&self, _level: bool) -> serialport::Result<()> {
+        Ok(())
+    }
+}
+
+pub struct Device {
+    port: Box<dyn Transport>,
     memory: MemoryMap,
 }
 
@@ -37,20 +160,12 @@ pub struct Data {
     forecast: String,
 }
 
-struct MemoryMap {
-    temperature_indoor: Memory,
-    temperature_outdoor: Memory,
-    dewpoint: Memory,
-    humidity_indoor: Memory,
-    humidity_outdoor: Memory,
-    wind_speed: Memory,
-    wind_dir: Memory,
-    wind_chill: Memory,
-    rain_1h: Memory,
-    rain_24h: Memory,
-    rain_total: Memory,
-    pressure: Memory,
-    tendency: Memory,
+/// Result of decoding an arbitrary named register (`Device::read_named`).
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged)]
+pub enum Reading {
+    Number(f32),
+    Text(String),
 }
 
 struct Memory {
@@ -59,228 +174,361 @@ struct Memory {
 }
 
 impl Device {
+    /// Final handshake byte the device sends to confirm a write completed.
+    const WRITE_ACK: u8 = 0x10;
+
     pub fn new(device: String) -> serialport::Result<Device> {
-        let memory = MemoryMap {
-            temperature_indoor: Memory {
-                address: 0x346,
-                size: 2,
-            },
-            temperature_outdoor: Memory {
-                address: 0x373,
-                size: 2,
-            },
-            dewpoint: Memory {
-                address: 0x3CE,
-                size: 2,
-            },
-            humidity_indoor: Memory {
-                address: 0x3FB,
-                size: 1,
-            },
-            humidity_outdoor: Memory {
-                address: 0x419,
-                size: 1,
-            },
-            wind_speed: Memory {
-                address: 0x529,
-                size: 3,
-            },
-            wind_dir: Memory {
-                address: 0x52C,
-                size: 1,
-            },
-            wind_chill: Memory {
-                address: 0x3A0,
-                size: 2,
-            },
-            rain_1h: Memory {
-                address: 0x4B4,
-                size: 3,
-            },
-            rain_24h: Memory {
-                address: 0x497,
-                size: 3,
-            },
-            rain_total: Memory {
-                address: 0x4D2,
-                size: 3,
-            },
-            pressure: Memory {
-                address: 0x5E2,
-                size: 3,
-            },
-            tendency: Memory {
-                address: 0x26B,
-                size: 1,
-            },
-        };
+        let transport = SerialTransport::open(device)?;
+
+        Ok(Self::with_transport(transport))
+    }
+
+    /// Build a `Device` against an arbitrary `Transport`, e.g. a `MockTransport`
+    /// in tests or a serial-over-IP bridge in place of a local port.
+    pub fn with_transport<T: Transport + 'static>(transport: T) -> Device {
+        Self::with_memory(transport, MemoryMap::default())
+    }
 
-        let device = Device {
-            port: Self::open(device)?.into(),
+    /// Build a `Device` against an arbitrary `Transport` and memory map, e.g.
+    /// a `MemoryMap::from_toml` for a firmware variant with shifted
+    /// addresses or extra sensors.
+    pub fn with_memory<T: Transport + 'static>(transport: T, memory: MemoryMap) -> Device {
+        Device {
+            port: Box::new(transport),
             memory,
-        };
+        }
+    }
+
+    /// Open `device` and load register definitions from `memory` instead of
+    /// the built-in map.
+    pub fn with_config(device: String, memory: MemoryMap) -> serialport::Result<Device> {
+        let transport = SerialTransport::open(device)?;
 
-        Ok(device)
+        Ok(Self::with_memory(transport, memory))
     }
 
-    fn open(device: String) -> serialport::Result<Box<dyn serialport::SerialPort>> {
-        let mut port = serialport::new(&device, 2_400)
-            .data_bits(serialport::DataBits::Eight)
-            .flow_control(serialport::FlowControl::None)
-            .parity(serialport::Parity::None)
-            .stop_bits(serialport::StopBits::One)
-            .timeout(Duration::from_secs(1))
-            .open()?;
+    /// Read and decode an arbitrary named register, e.g. one added through a
+    /// custom `MemoryMap::from_toml` config that has no dedicated getter.
+    pub fn read_named(&self, name: &str) -> serialport::Result<Reading> {
+        let spec = self.memory.get(name)?;
+        let value = self.try_read(&spec.memory())?;
 
-        Self::setup(&mut port)?;
+        spec.kind.decode(&value)
+    }
 
-        Ok(port)
+    fn number(reading: Reading) -> serialport::Result<f32> {
+        match reading {
+            Reading::Number(n) => Ok(n),
+            Reading::Text(_) => Err(serialport::Error::new(
+                serialport::ErrorKind::Io(std::io::ErrorKind::InvalidData),
+                "expected a numeric register",
+            )),
+        }
     }
 
-    fn setup(port: &mut Box<dyn serialport::SerialPort>) -> serialport::Result<()> {
-        port.write_request_to_send(true)?;
-        port.write_data_terminal_ready(false)?;
+    fn text(reading: Reading) -> serialport::Result<String> {
+        match reading {
+            Reading::Text(s) => Ok(s),
+            Reading::Number(_) => Err(serialport::Error::new(
+                serialport::ErrorKind::Io(std::io::ErrorKind::InvalidData),
+                "expected a labelled register",
+            )),
+        }
+    }
 
-        Ok(())
+    fn read_named_block(&self, name: &str) -> serialport::Result<Vec<u8>> {
+        let spec = self.memory.get(name)?;
+
+        self.read_block(spec.address, spec.size)
     }
 
+    /// Read every field of `Data` using a `read_block` per field (or, where
+    /// fields sit back-to-back or share a byte, one block shared between
+    /// them) rather than a fresh handshake for every getter. Only the
+    /// built-in register names that `Data` holds are read; other registers
+    /// in a custom memory map are not.
     pub fn read_all(&self) -> serialport::Result<Data> {
+        let tendency_spec = self.memory.get("tendency")?;
+        let forecast_spec = self.memory.get("forecast")?;
+        let tendency_block = self.read_block(tendency_spec.address, tendency_spec.size)?;
+
+        let temperature_indoor = self.read_named_block("temperature_indoor")?;
+        let temperature_outdoor = self.read_named_block("temperature_outdoor")?;
+        let dewpoint = self.read_named_block("dewpoint")?;
+        let humidity_indoor = self.read_named_block("humidity_indoor")?;
+        let humidity_outdoor = self.read_named_block("humidity_outdoor")?;
+        let wind_chill = self.read_named_block("wind_chill")?;
+        let rain_1h = self.read_named_block("rain_1h")?;
+        let rain_24h = self.read_named_block("rain_24h")?;
+        let rain_total = self.read_named_block("rain_total")?;
+        let pressure = self.read_named_block("pressure")?;
+
+        // wind_speed, wind_dir_degrees and wind_direction are adjacent in
+        // the memory map, so one block read covers all three.
+        let wind_speed_spec = self.memory.get("wind_speed")?;
+        let wind_dir_spec = self.memory.get("wind_dir_degrees")?;
+        let wind_direction_spec = self.memory.get("wind_direction")?;
+        let wind = self.read_block(
+            wind_speed_spec.address,
+            wind_speed_spec.size + wind_dir_spec.size,
+        )?;
+        let (wind_speed, wind_dir) = wind.split_at(wind_speed_spec.size);
+
+        let wind_dir_reading = wind_dir_spec.kind.decode(wind_dir)?;
+        let wind_direction_reading = wind_direction_spec.kind.decode(wind_dir)?;
+        let tendency_reading = tendency_spec.kind.decode(&tendency_block)?;
+        let forecast_reading = forecast_spec.kind.decode(&tendency_block)?;
+
         Ok(Data {
-            temperature_indoor: self.temperature_indoor()?,
-            temperature_outdoor: self.temperature_outdoor()?,
-            dewpoint: self.dewpoint()?,
-            humidity_indoor: self.humidity_indoor()?,
-            humidity_outdoor: self.humidity_outdoor()?,
-            wind_speed: self.wind_speed()?,
-            wind_dir: self.wind_dir()?,
-            wind_direction: self.wind_direction()?,
-            wind_chill: self.wind_chill()?,
-            rain_1h: self.rain_1h()?,
-            rain_24h: self.rain_24h()?,
-            rain_total: self.rain_total()?,
-            pressure: self.pressure()?,
-            tendency: self.tendency()?,
-            forecast: self.forecast()?,
+            temperature_indoor: Self::decode_temperature(&temperature_indoor),
+            temperature_outdoor: Self::decode_temperature(&temperature_outdoor),
+            dewpoint: Self::decode_temperature(&dewpoint),
+            humidity_indoor: Self::decode_humidity(&humidity_indoor),
+            humidity_outdoor: Self::decode_humidity(&humidity_outdoor),
+            wind_speed: Self::decode_wind_speed(wind_speed),
+            wind_dir: Self::number(wind_dir_reading)?,
+            wind_direction: Self::text(wind_direction_reading)?,
+            wind_chill: Self::decode_temperature(&wind_chill),
+            rain_1h: Self::decode_rain(&rain_1h),
+            rain_24h: Self::decode_rain(&rain_24h),
+            rain_total: Self::decode_rain(&rain_total),
+            pressure: Self::decode_pressure(&pressure),
+            tendency: Self::text(tendency_reading)?,
+            forecast: Self::text(forecast_reading)?,
+        })
+    }
+
+    /// Read `len` contiguous bytes starting at `address` in a single
+    /// handshake. The read opcode only carries a 4-bit size nibble, so at
+    /// most 15 bytes can be fetched per call; batching fields into one block
+    /// only pays off when they're close enough together to fit under that
+    /// cap (see `read_all`).
+    pub fn read_block(&self, address: u32, len: usize) -> serialport::Result<Vec<u8>> {
+        self.try_read(&Memory {
+            address,
+            size: len,
         })
     }
 
     pub fn temperature_indoor(&self) -> serialport::Result<f32> {
-        self.temperature(&self.memory.temperature_indoor)
+        self.temperature("temperature_indoor")
+    }
+
+    #[cfg(feature = "units")]
+    pub fn temperature_indoor_quantity(&self) -> serialport::Result<uom::si::f32::ThermodynamicTemperature> {
+        self.temperature_quantity("temperature_indoor")
     }
 
     pub fn temperature_outdoor(&self) -> serialport::Result<f32> {
-        self.temperature(&self.memory.temperature_outdoor)
+        self.temperature("temperature_outdoor")
+    }
+
+    #[cfg(feature = "units")]
+    pub fn temperature_outdoor_quantity(&self) -> serialport::Result<uom::si::f32::ThermodynamicTemperature> {
+        self.temperature_quantity("temperature_outdoor")
     }
 
     pub fn dewpoint(&self) -> serialport::Result<f32> {
-        self.temperature(&self.memory.dewpoint)
+        self.temperature("dewpoint")
+    }
+
+    #[cfg(feature = "units")]
+    pub fn dewpoint_quantity(&self) -> serialport::Result<uom::si::f32::ThermodynamicTemperature> {
+        self.temperature_quantity("dewpoint")
+    }
+
+    #[cfg(feature = "units")]
+    fn temperature_quantity(
+        &self,
+        name: &str,
+    ) -> serialport::Result<uom::si::f32::ThermodynamicTemperature> {
+        use uom::si::thermodynamic_temperature::degree_celsius;
+
+        Ok(uom::si::f32::ThermodynamicTemperature::new::<degree_celsius>(
+            self.temperature(name)?,
+        ))
     }
 
-    fn temperature(&self, memory: &Memory) -> serialport::Result<f32> {
-        let value = self.try_read(memory)?;
+    fn temperature(&self, name: &str) -> serialport::Result<f32> {
+        let spec = self.memory.get(name)?;
+        let value = self.try_read(&spec.memory())?;
 
+        Ok(Self::decode_temperature(&value))
+    }
+
+    fn decode_temperature(value: &[u8]) -> f32 {
         let low = (value[0] >> 4) as f32 / 10.0 + (value[0] & 0xF) as f32 / 100.0;
         let high = (value[1] >> 4) as f32 * 10.0 + (value[1] & 0xF) as f32;
 
-        Ok(Self::round(high + low - 30.0, 1))
+        Self::round(high + low - 30.0, 1)
     }
 
     pub fn humidity_indoor(&self) -> serialport::Result<u32> {
-        self.humidity(&self.memory.humidity_indoor)
+        self.humidity("humidity_indoor")
     }
 
     pub fn humidity_outdoor(&self) -> serialport::Result<u32> {
-        self.humidity(&self.memory.humidity_outdoor)
+        self.humidity("humidity_outdoor")
     }
 
-    fn humidity(&self, memory: &Memory) -> serialport::Result<u32> {
-        let value = self.try_read(memory)?;
+    fn humidity(&self, name: &str) -> serialport::Result<u32> {
+        let spec = self.memory.get(name)?;
+        let value = self.try_read(&spec.memory())?;
 
-        let low = (value[0] >> 4) as u32 * 10 + (value[0] & 0xF) as u32;
+        Ok(Self::decode_humidity(&value))
+    }
 
-        Ok(low)
+    fn decode_humidity(value: &[u8]) -> u32 {
+        (value[0] >> 4) as u32 * 10 + (value[0] & 0xF) as u32
     }
 
     pub fn wind_speed(&self) -> serialport::Result<f32> {
-        let value = self.try_read(&self.memory.wind_speed)?;
+        let spec = self.memory.get("wind_speed")?;
+        let value = self.try_read(&spec.memory())?;
 
-        Ok(((((value[1] & 0xF) as u16) << 8) as f32 + value[0] as f32) / 10.0)
+        Ok(Self::decode_wind_speed(&value))
+    }
+
+    fn decode_wind_speed(value: &[u8]) -> f32 {
+        ((((value[1] & 0xF) as u16) << 8) as f32 + value[0] as f32) / 10.0
+    }
+
+    #[cfg(feature = "units")]
+    pub fn wind_speed_quantity(&self) -> serialport::Result<uom::si::f32::Velocity> {
+        use uom::si::velocity::meter_per_second;
+
+        Ok(uom::si::f32::Velocity::new::<meter_per_second>(
+            self.wind_speed()?,
+        ))
     }
 
     pub fn wind_dir(&self) -> serialport::Result<f32> {
-        let value = self.try_read(&self.memory.wind_dir)?;
+        Self::number(self.read_named("wind_dir_degrees")?)
+    }
 
+    fn decode_wind_dir(value: &[u8]) -> f32 {
         let low = (value[0] >> 4) as f32;
 
-        Ok(Self::round(low * 22.5, 1))
+        Self::round(low * 22.5, 1)
     }
 
     pub fn wind_direction(&self) -> serialport::Result<String> {
-        let directions: Vec<&'static str> = vec![
-            "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
-            "NW", "NNW",
-        ];
-        let value = self.try_read(&self.memory.wind_dir)?;
-
-        let index: usize = (value[0] >> 4) as usize;
-
-        Ok(String::from(directions[index]))
+        Self::text(self.read_named("wind_direction")?)
     }
 
     pub fn wind_chill(&self) -> serialport::Result<f32> {
-        self.temperature(&self.memory.wind_chill)
+        self.temperature("wind_chill")
+    }
+
+    #[cfg(feature = "units")]
+    pub fn wind_chill_quantity(&self) -> serialport::Result<uom::si::f32::ThermodynamicTemperature> {
+        self.temperature_quantity("wind_chill")
     }
 
     pub fn rain_1h(&self) -> serialport::Result<f32> {
-        self.rain(&self.memory.rain_1h)
+        self.rain("rain_1h")
+    }
+
+    #[cfg(feature = "units")]
+    pub fn rain_1h_quantity(&self) -> serialport::Result<uom::si::f32::Length> {
+        self.rain_quantity("rain_1h")
     }
 
     pub fn rain_24h(&self) -> serialport::Result<f32> {
-        self.rain(&self.memory.rain_24h)
+        self.rain("rain_24h")
+    }
+
+    #[cfg(feature = "units")]
+    pub fn rain_24h_quantity(&self) -> serialport::Result<uom::si::f32::Length> {
+        self.rain_quantity("rain_24h")
     }
 
     pub fn rain_total(&self) -> serialport::Result<f32> {
-        self.rain(&self.memory.rain_total)
+        self.rain("rain_total")
+    }
+
+    #[cfg(feature = "units")]
+    pub fn rain_total_quantity(&self) -> serialport::Result<uom::si::f32::Length> {
+        self.rain_quantity("rain_total")
     }
 
-    fn rain(&self, memory: &Memory) -> serialport::Result<f32> {
-        let value = self.try_read(memory)?;
+    fn rain(&self, name: &str) -> serialport::Result<f32> {
+        let spec = self.memory.get(name)?;
+        let value = self.try_read(&spec.memory())?;
 
+        Ok(Self::decode_rain(&value))
+    }
+
+    fn decode_rain(value: &[u8]) -> f32 {
         let low = (value[0] >> 4) as f32 / 10.0 + (value[0] & 0xF) as f32 / 100.0;
         let med = (value[1] >> 4) as f32 * 10.0 + (value[1] & 0xF) as f32;
         let high = (value[2] >> 4) as f32 * 1000.0 + (value[2] & 0xF) as f32 * 100.0;
 
-        Ok(Self::round(low + med + high, 1))
+        Self::round(low + med + high, 1)
+    }
+
+    #[cfg(feature = "units")]
+    fn rain_quantity(&self, name: &str) -> serialport::Result<uom::si::f32::Length> {
+        use uom::si::length::millimeter;
+
+        Ok(uom::si::f32::Length::new::<millimeter>(self.rain(name)?))
     }
 
     pub fn pressure(&self) -> serialport::Result<f32> {
-        let value = self.try_read(&self.memory.pressure)?;
+        let spec = self.memory.get("pressure")?;
+        let value = self.try_read(&spec.memory())?;
+
+        Ok(Self::decode_pressure(&value))
+    }
 
+    fn decode_pressure(value: &[u8]) -> f32 {
         let low = (value[0] >> 4) as f32 + (value[0] & 0xF) as f32 / 10.0;
         let med = (value[1] >> 4) as f32 * 100.0 + (value[1] & 0xF) as f32 * 10.0;
         let high = (value[2] & 0xF) as f32 * 1000.0;
 
-        Ok(Self::round(low + med + high, 1))
+        Self::round(low + med + high, 1)
     }
 
-    pub fn tendency(&self) -> serialport::Result<String> {
-        let tendencies: Vec<&'static str> = vec!["Steady", "Rising", "Falling"];
+    #[cfg(feature = "units")]
+    pub fn pressure_quantity(&self) -> serialport::Result<uom::si::f32::Pressure> {
+        use uom::si::pressure::hectopascal;
 
-        let value = self.try_read(&self.memory.tendency)?;
-
-        let index = (value[0] >> 4) as usize;
+        Ok(uom::si::f32::Pressure::new::<hectopascal>(self.pressure()?))
+    }
 
-        Ok(String::from(tendencies[index]))
+    pub fn tendency(&self) -> serialport::Result<String> {
+        Self::text(self.read_named("tendency")?)
     }
 
     pub fn forecast(&self) -> serialport::Result<String> {
-        let forecasts: Vec<&'static str> = vec!["Rainy", "Cloudy", "Sunny"];
+        Self::text(self.read_named("forecast")?)
+    }
+
+    /// Zero out the station's total rain counter.
+    pub fn reset_rain_total(&self) -> serialport::Result<()> {
+        let size = self.memory.get("rain_total")?.size;
 
-        let value = self.try_read(&self.memory.tendency)?;
+        self.write_named("rain_total", &vec![0u8; size])
+    }
 
-        let index = (value[0] & 0xF) as usize;
+    /// Write `data` to an arbitrary named register, e.g. to set a display or
+    /// alarm value, or one added through a custom `MemoryMap::from_toml`
+    /// config that has no dedicated setter.
+    pub fn write_named(&self, name: &str, data: &[u8]) -> serialport::Result<()> {
+        let spec = self.memory.get(name)?;
+
+        if data.len() != spec.size {
+            return Err(serialport::Error::new(
+                serialport::ErrorKind::Io(std::io::ErrorKind::InvalidData),
+                format!(
+                    "register '{name}' is {} bytes, got {}",
+                    spec.size,
+                    data.len()
+                ),
+            ));
+        }
 
-        Ok(String::from(forecasts[index]))
+        self.try_write(&spec.memory(), data)
     }
 
     fn try_read(&self, memory: &Memory) -> serialport::Result<Vec<u8>> {
@@ -306,7 +554,6 @@ impl Device {
     fn read(&self, memory: &Memory) -> serialport::Result<Vec<u8>> {
         dbg_ws!("read: addr=0x{:X} size={}", memory.address, memory.size);
 
-        let mut response: Vec<u8> = Vec::with_capacity(memory.size);
         let mut buffer: [u8; 1] = [0; 1];
         let command = Self::encode_address(memory);
 
@@ -316,20 +563,17 @@ impl Device {
 
         for (i, c) in command.iter().enumerate().take(5) {
             dbg_ws!("write byte 0x{:02X} seq {}", c, i);
-            self.port.borrow_mut().write_all(&[*c])?;
-            self.port.borrow_mut().read_exact(&mut buffer[..])?;
+            self.port.write_all(&[*c])?;
+            self.port.read_exact(&mut buffer[..])?;
             dbg_ws!("echo byte 0x{:02X}", buffer[0]);
             Self::check(*c, i, buffer[0])?;
         }
 
-        for idx in 0..memory.size {
-            self.port.borrow_mut().read_exact(&mut buffer[..])?;
-            dbg_ws!("data[{}] = 0x{:02X}", idx, buffer[0]);
+        let mut response: Vec<u8> = vec![0; memory.size];
+        self.port.read_exact(&mut response)?;
+        dbg_ws!("data bytes: {:?}", response);
 
-            response.push(buffer[0]);
-        }
-
-        self.port.borrow_mut().read_exact(&mut buffer[..])?;
+        self.port.read_exact(&mut buffer[..])?;
         dbg_ws!("checksum read 0x{:02X}", buffer[0]);
 
         Self::check_data(buffer[0], response.clone())?;
@@ -339,6 +583,67 @@ impl Device {
         Ok(response)
     }
 
+    fn try_write(&self, memory: &Memory, data: &[u8]) -> serialport::Result<()> {
+        for i in 0..50 {
+            dbg_ws!("try_write attempt {} for address 0x{:X}", i, memory.address);
+            match self.write(memory, data) {
+                Ok(()) => {
+                    dbg_ws!("try_write success on attempt {}", i);
+                    return Ok(());
+                }
+                Err(e) => {
+                    dbg_ws!("try_write attempt {} failed: {:?}", i, e);
+                }
+            }
+        }
+
+        Err(serialport::Error::new(
+            serialport::ErrorKind::Io(std::io::ErrorKind::Other),
+            "Try write error",
+        ))
+    }
+
+    /// Write `data` to `memory`, mirroring `read`: after `reset`, send the
+    /// address handshake followed by a write opcode and the data nibbles
+    /// (each echoed back and validated like the read handshake, but against
+    /// the write acknowledgement formula in `check_write`), then check the
+    /// device's final acknowledgement byte.
+    fn write(&self, memory: &Memory, data: &[u8]) -> serialport::Result<()> {
+        dbg_ws!("write: addr=0x{:X} len={}", memory.address, data.len());
+
+        let mut buffer: [u8; 1] = [0; 1];
+        let command = Self::encode_write_command(memory, data.len());
+        let nibbles = Self::encode_data_nibbles(data);
+
+        dbg_ws!("write command bytes: {:?}", command);
+
+        self.reset()?;
+
+        for (i, c) in command.iter().chain(nibbles.iter()).enumerate() {
+            dbg_ws!("write byte 0x{:02X} seq {}", c, i);
+            self.port.write_all(&[*c])?;
+            self.port.read_exact(&mut buffer[..])?;
+            dbg_ws!("echo byte 0x{:02X}", buffer[0]);
+            Self::check_write(*c, i, buffer[0])?;
+        }
+
+        self.port.read_exact(&mut buffer[..])?;
+        dbg_ws!("write ack 0x{:02X}", buffer[0]);
+
+        if buffer[0] != Self::WRITE_ACK {
+            dbg_ws!("write ack mismatch: got 0x{:02X}", buffer[0]);
+
+            return Err(serialport::Error::new(
+                serialport::ErrorKind::Io(std::io::ErrorKind::Other),
+                "Write ack error",
+            ));
+        }
+
+        dbg_ws!("write succeeded");
+
+        Ok(())
+    }
+
     fn check(command: u8, sequence: usize, answer: u8) -> serialport::Result<()> {
         let checksum = if sequence < 4 {
             (sequence as u8) * 16 + (command - 0x82) / 4
@@ -364,6 +669,35 @@ impl Device {
         }
     }
 
+    /// Acknowledgement formula for the write handshake. The leading address
+    /// nibbles are echoed the same way as a read (`check`), but the write
+    /// opcode and every data nibble that follows live in the `0x12` command
+    /// family rather than `0xC2`, so they're validated against that base.
+    fn check_write(command: u8, sequence: usize, answer: u8) -> serialport::Result<()> {
+        let checksum = if sequence < 4 {
+            (sequence as u8) * 16 + (command - 0x82) / 4
+        } else {
+            0x30 + (command - 0x12) / 4
+        };
+
+        if checksum == answer {
+            Ok(())
+        } else {
+            dbg_ws!(
+                "check_write failed: cmd=0x{:02X} seq={} expected=0x{:02X} got=0x{:02X}",
+                command,
+                sequence,
+                checksum,
+                answer
+            );
+
+            Err(serialport::Error::new(
+                serialport::ErrorKind::Io(std::io::ErrorKind::Other),
+                "Check write error",
+            ))
+        }
+    }
+
     fn check_data(answer: u8, response: Vec<u8>) -> serialport::Result<()> {
         let mut checksum: u32 = 0;
 
@@ -394,13 +728,13 @@ impl Device {
         let mut buffer: [u8; 1] = [0; 1];
 
         for x in 0..100 {
-            self.port.borrow_mut().flush()?;
+            self.port.flush()?;
             dbg_ws!("reset: writing 0x06, attempt {}", x);
-            self.port.borrow_mut().write_all(&[0x06])?;
+            self.port.write_all(&[0x06])?;
 
             let mut reset_ok = false;
             for _ in 0..10 {
-                match self.port.borrow_mut().read_exact(&mut buffer[..]) {
+                match self.port.read_exact(&mut buffer[..]) {
                     Ok(_) => {
                         dbg_ws!("reset: read response 0x{:02X}", buffer[0]);
                         if buffer[0] == 0x01 {
@@ -438,17 +772,42 @@ impl Device {
         if memory.address == 0x06 {
             command = vec![0x06]
         } else {
-            for i in 0..4 {
-                let nibble = (memory.address >> (4 * (3 - i))) & 0x0F;
-                command.push(0x82 + (nibble * 4) as u8);
-            }
-
+            command.extend(Self::encode_address_nibbles(memory.address));
             command.push(std::cmp::min(0xC2 + memory.size * 4, 0xFE) as u8);
         }
 
         command
     }
 
+    fn encode_address_nibbles(address: u32) -> [u8; 4] {
+        let mut nibbles = [0u8; 4];
+
+        for (i, slot) in nibbles.iter_mut().enumerate() {
+            let nibble = (address >> (4 * (3 - i))) & 0x0F;
+            *slot = 0x82 + (nibble * 4) as u8;
+        }
+
+        nibbles
+    }
+
+    fn encode_write_command(memory: &Memory, data_len: usize) -> Vec<u8> {
+        let mut command: Vec<u8> = Self::encode_address_nibbles(memory.address).to_vec();
+        command.push(std::cmp::min(0x12 + data_len * 8, 0xFE) as u8);
+
+        command
+    }
+
+    fn encode_data_nibbles(data: &[u8]) -> Vec<u8> {
+        let mut command: Vec<u8> = Vec::with_capacity(data.len() * 2);
+
+        for byte in data {
+            command.push(0x12 + (byte >> 4) * 4);
+            command.push(0x12 + (byte & 0xF) * 4);
+        }
+
+        command
+    }
+
     fn round(x: f32, n: u32) -> f32 {
         let factor = 10u32.pow(n) as f32;
         let fract = (x.fract() * factor).round() / factor;
@@ -482,3 +841,102 @@ fn test_round() {
     assert_eq!(Device::round(-100.12345, 2), -100.12);
     assert_eq!(Device::round(100.12345, 5), 100.12345);
 }
+
+#[test]
+fn test_read_via_mock_transport() {
+    // reset: write 0x06, device replies "ready" straight away.
+    // handshake: 5 command bytes for address 0x346/size 2, each echoed with
+    // its expected checksum (see `check`), then the 2 data bytes and their
+    // checksum (see `check_data`).
+    let transport = MockTransport::new([0x02, 0, 19, 36, 54, 50, 0x12, 0x34, 0x46]);
+    let device = Device::with_transport(transport);
+
+    assert_eq!(device.temperature_indoor().unwrap(), 4.1);
+}
+
+#[test]
+fn test_reset_rain_total_via_mock_transport() {
+    // reset: write 0x06, device replies "ready" straight away.
+    // handshake: 4 address bytes + 1 write opcode for rain_total (0x4D2,
+    // size 3), each echoed with its expected checksum (see `check_write`),
+    // then 6 zero data nibbles (3 zero bytes), each echoed the same way,
+    // then the final write acknowledgement byte.
+    let transport = MockTransport::new([
+        0x02, 0, 20, 45, 50, 54, 48, 48, 48, 48, 48, 48, 0x10,
+    ]);
+    let device = Device::with_transport(transport);
+
+    assert!(device.reset_rain_total().is_ok());
+}
+
+#[test]
+fn test_write_named_sets_an_arbitrary_register() {
+    // reset: write 0x06, device replies "ready" straight away.
+    // handshake: 4 address bytes + 1 write opcode for wind_chill (0x3A0,
+    // size 2), each echoed with its expected checksum (see `check_write`),
+    // then the 4 data nibbles for [0x12, 0x34], each echoed the same way,
+    // then the final write acknowledgement byte.
+    let transport = MockTransport::new([0x02, 0, 19, 42, 48, 52, 49, 50, 51, 52, 0x10]);
+    let device = Device::with_transport(transport);
+
+    assert!(device.write_named("wind_chill", &[0x12, 0x34]).is_ok());
+}
+
+#[test]
+fn test_write_named_rejects_data_of_the_wrong_length() {
+    let transport = MockTransport::new([]);
+    let device = Device::with_transport(transport);
+
+    assert!(device.write_named("wind_chill", &[0x12]).is_err());
+}
+
+#[test]
+fn test_read_named_via_toml_memory_map() {
+    let path = std::env::temp_dir().join(format!("ws2300-test-memory-map-{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"
+        [relative_pressure]
+        address = 0x5E2
+        size = 3
+        decoder = "pressure"
+        "#,
+    )
+    .unwrap();
+
+    let memory = MemoryMap::from_toml(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // reset: write 0x06, device replies "ready" straight away.
+    // handshake: 5 command bytes for address 0x5E2/size 3, each echoed with
+    // its expected checksum (see `check`), then the 3 data bytes (decoding
+    // to 1010.0 hPa via `decode_pressure`) and their checksum (see
+    // `check_data`).
+    let transport = MockTransport::new([0x02, 0, 21, 46, 50, 51, 0xA0, 0x00, 0x01, 0xA1]);
+    let device = Device::with_memory(transport, memory);
+
+    match device.read_named("relative_pressure").unwrap() {
+        Reading::Number(n) => assert_eq!(n, 1010.0),
+        Reading::Text(_) => panic!("expected a numeric reading"),
+    }
+}
+
+#[test]
+fn test_from_toml_rejects_a_register_too_small_for_its_decoder() {
+    let path = std::env::temp_dir().join(format!("ws2300-test-undersized-{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"
+        [temperature_indoor]
+        address = 0x346
+        size = 1
+        decoder = "temperature"
+        "#,
+    )
+    .unwrap();
+
+    let result = MemoryMap::from_toml(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(result.is_err());
+}
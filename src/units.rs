@@ -0,0 +1,124 @@
+//! Rendering of `Device` readings as typed physical quantities (feature `units`).
+//!
+//! The plain `f32` getters on `Device` stay in fixed metric units; this module
+//! adds a parallel, unit-aware view on top of the `*_quantity()` getters so
+//! callers can ask for Fahrenheit, inHg, mph or inches without hand-rolling
+//! conversions.
+
+use crate::Device;
+use uom::si::length::{inch, millimeter};
+use uom::si::pressure::{hectopascal, inch_of_mercury};
+use uom::si::thermodynamic_temperature::{degree_celsius, degree_fahrenheit};
+use uom::si::velocity::{meter_per_second, mile_per_hour};
+
+/// Which unit system to render quantities in.
+#[derive(Clone, Copy, Debug)]
+pub enum System {
+    Metric,
+    Imperial,
+}
+
+/// A single reading rendered as a number plus its unit label.
+#[derive(serde::Serialize)]
+pub struct Measurement {
+    pub value: f32,
+    pub unit: &'static str,
+}
+
+impl Measurement {
+    fn temperature(value: uom::si::f32::ThermodynamicTemperature, system: System) -> Measurement {
+        match system {
+            System::Metric => Measurement {
+                value: value.get::<degree_celsius>(),
+                unit: "°C",
+            },
+            System::Imperial => Measurement {
+                value: value.get::<degree_fahrenheit>(),
+                unit: "°F",
+            },
+        }
+    }
+
+    fn velocity(value: uom::si::f32::Velocity, system: System) -> Measurement {
+        match system {
+            System::Metric => Measurement {
+                value: value.get::<meter_per_second>(),
+                unit: "m/s",
+            },
+            System::Imperial => Measurement {
+                value: value.get::<mile_per_hour>(),
+                unit: "mph",
+            },
+        }
+    }
+
+    fn length(value: uom::si::f32::Length, system: System) -> Measurement {
+        match system {
+            System::Metric => Measurement {
+                value: value.get::<millimeter>(),
+                unit: "mm",
+            },
+            System::Imperial => Measurement {
+                value: value.get::<inch>(),
+                unit: "in",
+            },
+        }
+    }
+
+    fn pressure(value: uom::si::f32::Pressure, system: System) -> Measurement {
+        match system {
+            System::Metric => Measurement {
+                value: value.get::<hectopascal>(),
+                unit: "hPa",
+            },
+            System::Imperial => Measurement {
+                value: value.get::<inch_of_mercury>(),
+                unit: "inHg",
+            },
+        }
+    }
+}
+
+/// Unit-aware counterpart of `crate::Data`, with each temperature, wind
+/// speed, rain and pressure reading rendered as a `Measurement` in the
+/// requested `System`. Humidity (percent) and `wind_dir` (degrees) stay
+/// plain numbers.
+#[derive(serde::Serialize)]
+pub struct Data {
+    pub temperature_indoor: Measurement,
+    pub temperature_outdoor: Measurement,
+    pub dewpoint: Measurement,
+    pub humidity_indoor: u32,
+    pub humidity_outdoor: u32,
+    pub wind_speed: Measurement,
+    pub wind_dir: f32,
+    pub wind_direction: String,
+    pub wind_chill: Measurement,
+    pub rain_1h: Measurement,
+    pub rain_24h: Measurement,
+    pub rain_total: Measurement,
+    pub pressure: Measurement,
+    pub tendency: String,
+    pub forecast: String,
+}
+
+/// Read every field off `device` and render it in `system`.
+pub fn read_all(device: &Device, system: System) -> serialport::Result<Data> {
+    Ok(Data {
+        temperature_indoor: Measurement::temperature(device.temperature_indoor_quantity()?, system),
+        temperature_outdoor: Measurement::temperature(device.temperature_outdoor_quantity()?, system),
+        dewpoint: Measurement::temperature(device.dewpoint_quantity()?, system),
+        humidity_indoor: device.humidity_indoor()?,
+        humidity_outdoor: device.humidity_outdoor()?,
+        wind_speed: Measurement::velocity(device.wind_speed_quantity()?, system),
+        wind_dir: device.wind_dir()?,
+        wind_direction: device.wind_direction()?,
+        wind_chill: Measurement::temperature(device.wind_chill_quantity()?, system),
+        rain_1h: Measurement::length(device.rain_1h_quantity()?, system),
+        rain_24h: Measurement::length(device.rain_24h_quantity()?, system),
+        rain_total: Measurement::length(device.rain_total_quantity()?, system),
+        pressure: Measurement::pressure(device.pressure_quantity()?, system),
+        tendency: device.tendency()?,
+        forecast: device.forecast()?,
+    })
+}